@@ -2,6 +2,94 @@ use structopt::StructOpt;
 use std::io::prelude::*;
 
 
+#[derive(Debug, Clone, Copy)]
+enum Format
+{
+    Json,
+    Yaml,
+    Json5,
+    Msgpack,
+}
+
+impl Format
+{
+    fn is_binary(&self) -> bool
+    {
+        matches!(self, Format::Msgpack)
+    }
+}
+
+/// Serialize any value with the chosen backend, returning the raw bytes so
+/// both textual and binary formats can share a single write path.
+fn serialize<T: serde::Serialize>(value: &T, format: Format) -> Result<Vec<u8>, String>
+{
+    match format
+    {
+        Format::Json    => serde_json::to_string_pretty(value).map(String::into_bytes).map_err(|e| e.to_string()),
+        Format::Yaml    => serde_yaml::to_string(value).map(String::into_bytes).map_err(|e| e.to_string()),
+        Format::Json5   => json5::to_string(value).map(String::into_bytes).map_err(|e| e.to_string()),
+        Format::Msgpack => rmp_serde::to_vec(value).map_err(|e| e.to_string()),
+    }
+}
+
+/// Write serialized `bytes` either to stdout (textual formats get a trailing
+/// newline) or to the requested output file.
+fn emit(bytes: &[u8], format: Format, out: &Option<std::path::PathBuf>)
+{
+    match out
+    {
+        None => {
+            let stdout = std::io::stdout();
+            let mut handle = stdout.lock();
+            let result = if format.is_binary()
+            {
+                handle.write_all(bytes)
+            }
+            else
+            {
+                writeln!(handle, "{}", String::from_utf8_lossy(bytes))
+            };
+            if let Err(e) = result
+            {
+                eprintln!("Failed to write to stdout! Reason: {}", e);
+                std::process::exit(6);
+            }
+        }
+        Some(path) => {
+            let mut file = match std::fs::File::create(path)
+            {
+                Ok(file) => file,
+                Err(e) => {
+                    eprintln!("Failed to open file for output! Reason: {}", e);
+                    std::process::exit(7);
+                }
+            };
+            if let Err(e) = file.write_all(bytes)
+            {
+                eprintln!("Failed to save output to file! Reason: {}", e);
+                std::process::exit(8);
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for Format
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        match s.to_ascii_lowercase().as_str()
+        {
+            "json"    => Ok(Format::Json),
+            "yaml"    => Ok(Format::Yaml),
+            "json5"   => Ok(Format::Json5),
+            "msgpack" => Ok(Format::Msgpack),
+            other     => Err(format!("unknown format: {}", other)),
+        }
+    }
+}
+
 #[derive(StructOpt, Debug)]
 #[structopt(name = "tmsim-converter", about = "Converter of human readable turing machine commands into json")]
 struct Options
@@ -11,6 +99,24 @@ struct Options
 
     #[structopt(short, long, parse(from_os_str))]
     out: Option<std::path::PathBuf>,
+
+    #[structopt(short, long, default_value = "json", possible_values = &["json", "yaml", "json5", "msgpack"])]
+    format: Format,
+
+    #[structopt(long)]
+    decode: bool,
+
+    #[structopt(long)]
+    strict: bool,
+
+    #[structopt(long)]
+    validate: bool,
+
+    #[structopt(long)]
+    run: bool,
+
+    #[structopt(long, default_value = "1000")]
+    max_steps: usize,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -25,7 +131,7 @@ pub struct Command
 }
 
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, serde::Deserialize)]
 struct TMachineConfiguration
 {
     commands: std::vec::Vec<Command>,
@@ -33,49 +139,457 @@ struct TMachineConfiguration
     tape: Option<String>,
 }
 
-fn parse_alphabet_or_tape(line: &str, is_tape: bool) -> String
+/// Error raised when a line matches one of the regexes but cannot be turned
+/// into a usable value (a bad state number, a missing symbol, ...).
+#[derive(Debug)]
+struct ParseError
 {
-    let uncleaned = line.split('(').collect::<Vec<_>>()[1].trim_end_matches(')');
+    reason: String,
+}
+
+impl std::fmt::Display for ParseError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    {
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl ParseError
+{
+    fn new(reason: impl Into<String>) -> Self
+    {
+        ParseError { reason: reason.into() }
+    }
+}
+
+/// A line that could not be used, remembered so it can be reported at the end.
+struct Diagnostic
+{
+    line_number: usize,
+    raw_text: String,
+    reason: String,
+}
+
+fn parse_alphabet_or_tape(line: &str, is_tape: bool) -> Result<String, ParseError>
+{
+    let uncleaned = line.split('(').nth(1)
+        .ok_or_else(|| ParseError::new("missing opening parenthesis"))?
+        .trim_end_matches(')');
     let mut chars: Vec<char>= uncleaned.chars().collect();
     if !is_tape
     {
         chars.sort_unstable();
         chars.dedup();
     }
-    String::from_iter(chars.iter())
+    Ok(String::from_iter(chars.iter()))
 }
 
-fn parse_command(line: &str) -> Command
+fn parse_command(line: &str) -> Result<Command, ParseError>
 {
     let parts = line.split("->").collect::<Vec<_>>();
     let left_part = parts[0].trim().trim_start_matches('q');
-    let right_part = parts[1].trim().trim_start_matches('q');
+    let right_part = parts.get(1)
+        .ok_or_else(|| ParseError::new("missing '->' separator"))?
+        .trim().trim_start_matches('q');
 
     let left_split = left_part.split('(').collect::<Vec<_>>();
-    let state_num:usize = left_split[0].trim().parse().unwrap();
-    let symbol = left_split[1].trim_end_matches(')').chars().collect::<Vec<_>>()[0];
+    let state_num: usize = left_split[0].trim().parse()
+        .map_err(|_| ParseError::new(format!("invalid state number '{}'", left_split[0].trim())))?;
+    let symbol = left_split.get(1)
+        .and_then(|s| s.trim_end_matches(')').chars().next())
+        .ok_or_else(|| ParseError::new("missing reading symbol"))?;
 
     let right_split = right_part.split('(').collect::<Vec<_>>();
-    let new_state_num:usize = right_split[0].trim().parse().unwrap();
+    let new_state_num: usize = right_split[0].trim().parse()
+        .map_err(|_| ParseError::new(format!("invalid state number '{}'", right_split[0].trim())))?;
 
-    let sym_move_split = right_split[1].split(')').collect::<Vec<_>>();
-    let new_symbol = sym_move_split[0].chars().collect::<Vec<_>>()[0];
-    let movment = match sym_move_split[1].chars().collect::<Vec<_>>()[0]
+    let sym_move_split = right_split.get(1)
+        .ok_or_else(|| ParseError::new("missing symbol/movement group"))?
+        .split(')').collect::<Vec<_>>();
+    let new_symbol = sym_move_split[0].chars().next()
+        .ok_or_else(|| ParseError::new("missing place symbol"))?;
+    let movment = match sym_move_split.get(1).and_then(|s| s.chars().next())
     {
-        'R' => "Right",
-        'L' => "Left",
-        'S' => "Stop",
+        Some('R') => "Right",
+        Some('L') => "Left",
+        Some('S') => "Stop",
         _ => "Stop" //? Just for sure
     }.to_string();
 
-    Command{state: state_num, next_state: new_state_num, reading_char: symbol, place_char: new_symbol, next_move: movment}
+    Ok(Command{state: state_num, next_state: new_state_num, reading_char: symbol, place_char: new_symbol, next_move: movment})
+
+}
+
+/// Iterator adapter that joins backslash-continued physical lines into a single
+/// logical line, so a long transition table can be split across several lines.
+/// Each yielded item carries the 1-based physical line number the logical line
+/// started on, so diagnostics can point back at the real source position.
+struct ContinuationLines<R>
+    where R: Iterator<Item = std::io::Result<String>>
+{
+    inner: R,
+    consumed: usize,
+}
+
+impl<R> ContinuationLines<R>
+    where R: Iterator<Item = std::io::Result<String>>
+{
+    fn new(inner: R) -> Self
+    {
+        ContinuationLines { inner, consumed: 0 }
+    }
+
+    /// Pull the next physical line, counting it, so `consumed` always reflects
+    /// how many physical lines have been read.
+    fn advance(&mut self) -> Option<std::io::Result<String>>
+    {
+        let item = self.inner.next();
+        if item.is_some()
+        {
+            self.consumed += 1;
+        }
+        item
+    }
+
+    /// Pull the next physical line and append it to `past`, recursing while the
+    /// accumulated text keeps ending with a backslash. A trailing backslash on
+    /// the very last line (EOF reached) simply yields what we have so far.
+    fn join_next(&mut self, start: usize, past: String) -> Option<std::io::Result<(usize, String)>>
+    {
+        match self.advance()
+        {
+            None => Some(Ok((start, past))),
+            Some(Err(e)) => Some(Err(e)),
+            Some(Ok(line)) => {
+                let combined = format!("{}{}", past, line.trim());
+                if combined.ends_with('\\')
+                {
+                    self.join_next(start, combined.trim_end_matches('\\').to_string())
+                }
+                else
+                {
+                    Some(Ok((start, combined)))
+                }
+            }
+        }
+    }
+}
+
+impl<R> Iterator for ContinuationLines<R>
+    where R: Iterator<Item = std::io::Result<String>>
+{
+    type Item = std::io::Result<(usize, String)>;
+
+    fn next(&mut self) -> Option<Self::Item>
+    {
+        match self.advance()
+        {
+            None => None,
+            Some(Err(e)) => Some(Err(e)),
+            Some(Ok(line)) => {
+                let start = self.consumed;
+                let trimmed = line.trim().to_string();
+                if trimmed.ends_with('\\')
+                {
+                    self.join_next(start, trimmed.trim_end_matches('\\').to_string())
+                }
+                else
+                {
+                    Some(Ok((start, trimmed)))
+                }
+            }
+        }
+    }
+}
+
+fn render_source(conf: &TMachineConfiguration) -> String
+{
+    let mut lines: Vec<String> = Vec::new();
+
+    for command in &conf.commands
+    {
+        let movment = match command.next_move.as_str()
+        {
+            "Right" => "R",
+            "Left"  => "L",
+            "Stop"  => "S",
+            _       => "S" //? Just for sure
+        };
+        lines.push(format!(
+            "q{}({}) -> q{}({}){}",
+            command.state, command.reading_char,
+            command.next_state, command.place_char, movment
+        ));
+    }
+
+    if let Some(alphabet) = &conf.alphabet
+    {
+        lines.push(format!("alphabet: ({})", alphabet));
+    }
+
+    if let Some(tape) = &conf.tape
+    {
+        lines.push(format!("tape: ({})", tape));
+    }
 
+    lines.join("\n")
+}
+
+fn decode(options: &Options)
+{
+    let bytes = match std::fs::read(&options.source)
+    {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("Failed to open file! Reason: {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    let conf: TMachineConfiguration = match options.format
+    {
+        Format::Json    => serde_json::from_slice(&bytes).map_err(|e| e.to_string()),
+        Format::Yaml    => serde_yaml::from_slice(&bytes).map_err(|e| e.to_string()),
+        Format::Json5   => String::from_utf8(bytes).map_err(|e| e.to_string())
+                               .and_then(|s| json5::from_str(&s).map_err(|e| e.to_string())),
+        Format::Msgpack => rmp_serde::from_slice(&bytes).map_err(|e| e.to_string()),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("Error occured while decoding {:?}! Reason: {}", options.format, e);
+        std::process::exit(6);
+    });
+
+    let source = render_source(&conf);
+
+    match &options.out
+    {
+        None => println!("{}", source),
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, format!("{}\n", source))
+            {
+                eprintln!("Failed to save output to file! Reason: {}", e);
+                std::process::exit(8);
+            }
+        }
+    }
+}
+
+/// Run the semantic checks described for `--validate` over an assembled
+/// machine. Prints a grouped report and returns `true` when a hard error
+/// (non-deterministic transition or an out-of-alphabet symbol) was found;
+/// unreachable states are reported as warnings only.
+fn validate(conf: &TMachineConfiguration) -> bool
+{
+    let mut errors: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+
+    // (1) determinism: at most one transition per (state, reading_char).
+    let mut seen: std::collections::HashMap<(usize, char), &Command> = std::collections::HashMap::new();
+    for command in &conf.commands
+    {
+        let key = (command.state, command.reading_char);
+        if let Some(previous) = seen.get(&key)
+        {
+            errors.push(format!(
+                "non-deterministic transition for q{}({}): -> q{} and -> q{}",
+                command.state, command.reading_char, previous.next_state, command.next_state
+            ));
+        }
+        else
+        {
+            seen.insert(key, command);
+        }
+    }
+
+    // (2) alphabet coverage: every symbol read or written must be declared.
+    //     The tape's blank marker '*' is always allowed. With no alphabet line
+    //     there is nothing to check against, so note it once rather than
+    //     flagging every symbol (the missing alphabet is reported separately).
+    match &conf.alphabet
+    {
+        None => warnings.push("no alphabet declared, skipping coverage check".to_string()),
+        Some(alphabet) => {
+            let alphabet: std::collections::HashSet<char> = alphabet.chars().collect();
+            for command in &conf.commands
+            {
+                for (label, symbol) in [("reading", command.reading_char), ("place", command.place_char)]
+                {
+                    if symbol != '*' && !alphabet.contains(&symbol)
+                    {
+                        errors.push(format!(
+                            "q{}: {} symbol '{}' is not in the alphabet",
+                            command.state, label, symbol
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    // (3) reachability: BFS from state 0 over state -> next_state edges.
+    let mut adjacency: std::collections::HashMap<usize, Vec<usize>> = std::collections::HashMap::new();
+    let mut declared: std::collections::HashSet<usize> = std::collections::HashSet::new();
+    for command in &conf.commands
+    {
+        adjacency.entry(command.state).or_default().push(command.next_state);
+        declared.insert(command.state);
+        declared.insert(command.next_state);
+    }
+
+    let mut visited: std::collections::HashSet<usize> = std::collections::HashSet::new();
+    let mut queue: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+    if declared.contains(&0)
+    {
+        queue.push_back(0);
+        visited.insert(0);
+    }
+    while let Some(state) = queue.pop_front()
+    {
+        if let Some(next_states) = adjacency.get(&state)
+        {
+            for &next in next_states
+            {
+                if visited.insert(next)
+                {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    let mut unreachable: Vec<usize> = declared.difference(&visited).copied().collect();
+    unreachable.sort_unstable();
+    for state in unreachable
+    {
+        warnings.push(format!("state q{} is declared but not reachable from q0", state));
+    }
+
+    if !errors.is_empty()
+    {
+        eprintln!("errors:");
+        for error in &errors
+        {
+            eprintln!("  {}", error);
+        }
+    }
+    if !warnings.is_empty()
+    {
+        eprintln!("warnings:");
+        for warning in &warnings
+        {
+            eprintln!("  {}", warning);
+        }
+    }
+    if errors.is_empty() && warnings.is_empty()
+    {
+        eprintln!("validation passed");
+    }
+
+    !errors.is_empty()
+}
+
+/// A single snapshot of the machine during a `--run` simulation.
+#[derive(serde::Serialize)]
+struct Configuration
+{
+    step: usize,
+    state: usize,
+    head: usize,
+    tape: String,
+}
+
+/// Execute the machine starting in state 0 at the first non-blank tape cell,
+/// returning a trace of the configuration seen before each step plus a final
+/// snapshot of the halting configuration. The tape is grown with the blank
+/// symbol `*` whenever the head runs past either end; the run stops when no
+/// command matches `(state, symbol)`, a `Stop` move is taken, or `max_steps`
+/// steps have elapsed.
+fn run_machine(conf: &TMachineConfiguration, max_steps: usize) -> Vec<Configuration>
+{
+    const BLANK: char = '*';
+
+    let mut tape: Vec<char> = conf.tape.as_ref().map(|t| t.chars().collect()).unwrap_or_default();
+    if tape.is_empty()
+    {
+        tape.push(BLANK);
+    }
+
+    let mut transitions: std::collections::HashMap<(usize, char), &Command> = std::collections::HashMap::new();
+    for command in &conf.commands
+    {
+        transitions.entry((command.state, command.reading_char)).or_insert(command);
+    }
+
+    // Skip the leading blank marker so the head starts on the actual input.
+    let mut head: usize = tape.iter().position(|&c| c != BLANK).unwrap_or(0);
+    let mut state: usize = 0;
+    let mut trace: Vec<Configuration> = Vec::new();
+
+    for step in 0..max_steps
+    {
+        trace.push(Configuration { step, state, head, tape: tape.iter().collect() });
+
+        let current_char = tape[head];
+        let command = match transitions.get(&(state, current_char))
+        {
+            Some(command) => *command,
+            None => return trace, // no transition: the snapshot above is the halt
+        };
+
+        tape[head] = command.place_char;
+        state = command.next_state;
+
+        let halted = match command.next_move.as_str()
+        {
+            "Right" => {
+                head += 1;
+                if head == tape.len()
+                {
+                    tape.push(BLANK);
+                }
+                false
+            }
+            "Left" => {
+                if head == 0
+                {
+                    tape.insert(0, BLANK);
+                }
+                else
+                {
+                    head -= 1;
+                }
+                false
+            }
+            _ => true, // "Stop" (or anything unexpected) halts the machine
+        };
+
+        if halted
+        {
+            trace.push(Configuration { step: step + 1, state, head, tape: tape.iter().collect() });
+            break;
+        }
+    }
+
+    trace
 }
 
 fn main()
 {
     let options = Options::from_args();
 
+    if !options.source.exists()
+    {
+        eprintln!("Specified file does not exists!");
+        std::process::exit(1);
+    }
+
+    if options.decode
+    {
+        decode(&options);
+        return;
+    }
+
     let mut tmachineconf = TMachineConfiguration{
         commands: std::vec::Vec::new(),
         alphabet: None,
@@ -88,12 +602,6 @@ fn main()
 
 
 
-    if !options.source.exists()
-    {
-        eprintln!("Specified file does not exists!");
-        std::process::exit(1);
-    }
-
     let opened_source = match std::fs::File::open(&options.source)
     {
         Ok(file) => file,
@@ -105,33 +613,70 @@ fn main()
 
     let bufreader = std::io::BufReader::new(opened_source);
 
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
 
-    for line in bufreader.lines()
+    for item in ContinuationLines::new(bufreader.lines())
     {
-        let line = match line 
+        let (line_number, line) = match item
         {
-            Ok(line) => line.trim().to_string(),
-            Err(e) => 
+            Ok((line_number, line)) => (line_number, line.trim().to_string()),
+            Err(e) =>
             {
                 eprintln!("Failed to read next line! Reason: {}", e);
                 std::process::exit(3);
             }
         };
 
+        if line.is_empty()
+        {
+            continue;
+        }
 
         if command_pattern.is_match(&line)
         {
-            let new_command = parse_command(&line);
-            tmachineconf.commands.push(new_command);
+            match parse_command(&line)
+            {
+                Ok(new_command) => tmachineconf.commands.push(new_command),
+                Err(e) => diagnostics.push(Diagnostic { line_number, raw_text: line, reason: e.to_string() }),
+            }
         }
         else if alphabet_pattern.is_match(&line)
         {
-            tmachineconf.alphabet = Some(parse_alphabet_or_tape(&line, false));
+            match parse_alphabet_or_tape(&line, false)
+            {
+                Ok(alphabet) => tmachineconf.alphabet = Some(alphabet),
+                Err(e) => diagnostics.push(Diagnostic { line_number, raw_text: line, reason: e.to_string() }),
+            }
         }
         else if tape_pattern.is_match(&line)
         {
-            tmachineconf.tape = Some(parse_alphabet_or_tape(&line, true));
+            match parse_alphabet_or_tape(&line, true)
+            {
+                Ok(tape) => tmachineconf.tape = Some(tape),
+                Err(e) => diagnostics.push(Diagnostic { line_number, raw_text: line, reason: e.to_string() }),
+            }
         }
+        else
+        {
+            diagnostics.push(Diagnostic { line_number, raw_text: line, reason: "line matches no known pattern".to_string() });
+        }
+    }
+
+    let source_display = options.source.display();
+    for diagnostic in &diagnostics
+    {
+        eprintln!("{}:{}: {} ({})", source_display, diagnostic.line_number, diagnostic.reason, diagnostic.raw_text);
+    }
+
+    if options.strict && !diagnostics.is_empty()
+    {
+        eprintln!("{} diagnostic(s) recorded, aborting due to --strict", diagnostics.len());
+        std::process::exit(9);
+    }
+
+    if options.validate && validate(&tmachineconf)
+    {
+        std::process::exit(10);
     }
 
     if tmachineconf.alphabet.is_none()
@@ -146,36 +691,122 @@ fn main()
         std::process::exit(5);
     }
 
-    if options.out.is_none()
+    if options.run
     {
-        match serde_json::to_string_pretty(&tmachineconf)
-        {
-            Ok(s) => println!("{}", s),
-            Err(e) => {
-                eprintln!("Error occured while converting to json! Reason: {}", e);
-                std::process::exit(6)
-            }
-        }
+        let trace = run_machine(&tmachineconf, options.max_steps);
+        let serialized = serialize(&trace, options.format)
+            .unwrap_or_else(|e| {
+                eprintln!("Error occured while converting trace to {:?}! Reason: {}", options.format, e);
+                std::process::exit(6);
+            });
+        emit(&serialized, options.format, &options.out);
+        return;
     }
-    else
+
+    let serialized = serialize(&tmachineconf, options.format)
+        .unwrap_or_else(|e| {
+            eprintln!("Error occured while converting to {:?}! Reason: {}", options.format, e);
+            std::process::exit(6);
+        });
+
+    emit(&serialized, options.format, &options.out);
+
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn command(state: usize, reading: char, next_state: usize, place: char, mv: &str) -> Command
     {
-        let file = match std::fs::File::create(&options.out.unwrap())
-            {
-                Ok(file) => file,
-                Err(e) => {
-                    eprintln!("Failed to open file for output! Reason: {}", e);
-                    std::process::exit(7);
-                }
-            };
-            match serde_json::to_writer(&file, &tmachineconf)
-            {
-                Ok(()) => {},
-                Err(e) => 
-                {
-                    eprintln!("Failed to save json to file! Reason: {}", e);
-                    std::process::exit(8);
-                }
-            }
+        Command { state, next_state, reading_char: reading, place_char: place, next_move: mv.to_string() }
+    }
+
+    #[test]
+    fn continuation_lines_trailing_backslash_at_eof_yields_line()
+    {
+        // A backslash on the very last line before EOF must not hang or drop
+        // the line; it is yielded (backslash stripped) as-is.
+        let input: Vec<std::io::Result<String>> = vec![Ok("q0(a)\\".to_string())];
+        let joined: Vec<(usize, String)> = ContinuationLines::new(input.into_iter())
+            .map(|item| item.unwrap())
+            .collect();
+        assert_eq!(joined, vec![(1, "q0(a)".to_string())]);
+    }
+
+    #[test]
+    fn continuation_lines_joins_and_tracks_physical_line()
+    {
+        let input: Vec<std::io::Result<String>> = vec![
+            Ok("q0(a) ->\\".to_string()),
+            Ok("q1(b)R".to_string()),
+            Ok("tape: (*a*)".to_string()),
+        ];
+        let joined: Vec<(usize, String)> = ContinuationLines::new(input.into_iter())
+            .map(|item| item.unwrap())
+            .collect();
+        // The joined logical line keeps its starting physical line number (1),
+        // and the following line is correctly numbered 3, not 2.
+        assert_eq!(joined, vec![
+            (1, "q0(a) ->q1(b)R".to_string()),
+            (3, "tape: (*a*)".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn render_source_round_trips_through_parse()
+    {
+        let conf = TMachineConfiguration {
+            commands: vec![
+                command(0, 'a', 1, 'b', "Right"),
+                command(1, 'b', 1, 'b', "Stop"),
+            ],
+            alphabet: Some("ab".to_string()),
+            tape: Some("*ab*".to_string()),
+        };
+
+        let text = render_source(&conf);
+        let lines: Vec<&str> = text.lines().collect();
+
+        let first = parse_command(lines[0]).unwrap();
+        assert_eq!(first.state, 0);
+        assert_eq!(first.reading_char, 'a');
+        assert_eq!(first.next_state, 1);
+        assert_eq!(first.place_char, 'b');
+        assert_eq!(first.next_move, "Right");
+
+        let second = parse_command(lines[1]).unwrap();
+        assert_eq!(second.next_move, "Stop");
+
+        assert_eq!(parse_alphabet_or_tape(lines[2], false).unwrap(), "ab");
+        assert_eq!(parse_alphabet_or_tape(lines[3], true).unwrap(), "*ab*");
     }
 
+    #[test]
+    fn run_machine_multi_step_flips_bits_and_emits_halt()
+    {
+        // Walk right over the input flipping 0<->1, halt on the trailing blank.
+        let conf = TMachineConfiguration {
+            commands: vec![
+                command(0, '0', 0, '1', "Right"),
+                command(0, '1', 0, '0', "Right"),
+                command(0, '*', 1, '*', "Stop"),
+            ],
+            alphabet: Some("01".to_string()),
+            tape: Some("*01*".to_string()),
+        };
+
+        let trace = run_machine(&conf, 100);
+
+        // Head skips the leading blank, so the first read is '0' in state 0.
+        assert_eq!(trace.first().map(|c| c.head), Some(1));
+        // Several steps actually ran instead of halting immediately.
+        assert!(trace.len() > 2);
+
+        // The halting configuration is emitted and reflects the final tape.
+        let last = trace.last().expect("non-empty trace");
+        assert_eq!(last.state, 1);
+        assert_eq!(last.tape, "*10*");
+    }
 }
\ No newline at end of file